@@ -0,0 +1,114 @@
+//! Camera and per-object transform uniforms.
+
+use glam::{Mat4, Vec3};
+
+/// Converts OpenGL's `[-1, 1]` NDC depth range to wgpu's `[0, 1]`.
+#[rustfmt::skip]
+pub const OPENGL_TO_WGPU_MATRIX: Mat4 = Mat4::from_cols_array(&[
+    1.0, 0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0, 0.0,
+    0.0, 0.0, 0.5, 0.0,
+    0.0, 0.0, 0.5, 1.0,
+]);
+
+/// A perspective camera that produces a view-projection matrix.
+pub struct Camera {
+    pub position: Vec3,
+    pub target: Vec3,
+    pub up: Vec3,
+    pub fovy: f32,
+    pub aspect: f32,
+    pub znear: f32,
+    pub zfar: f32,
+}
+
+impl Camera {
+    pub fn new(aspect: f32) -> Self {
+        Self {
+            position: Vec3::new(0.0, 1.5, 4.0),
+            target: Vec3::ZERO,
+            up: Vec3::Y,
+            fovy: 45f32.to_radians(),
+            aspect,
+            znear: 0.1,
+            zfar: 100.0,
+        }
+    }
+
+    pub fn view_proj(&self) -> Mat4 {
+        let view = Mat4::look_at_rh(self.position, self.target, self.up);
+        let proj = Mat4::perspective_rh(self.fovy, self.aspect, self.znear, self.zfar);
+        OPENGL_TO_WGPU_MATRIX * proj * view
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CameraUniform {
+    pub view_proj: [[f32; 4]; 4],
+}
+
+impl CameraUniform {
+    pub fn new() -> Self {
+        Self {
+            view_proj: Mat4::IDENTITY.to_cols_array_2d(),
+        }
+    }
+
+    pub fn update(&mut self, camera: &Camera) {
+        self.view_proj = camera.view_proj().to_cols_array_2d();
+    }
+}
+
+impl Default for CameraUniform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Per-mesh model matrix plus its inverse-transpose for transforming normals.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ModelUniform {
+    pub model: [[f32; 4]; 4],
+    pub normal_matrix: [[f32; 4]; 4],
+}
+
+impl ModelUniform {
+    pub fn new(model: Mat4) -> Self {
+        let normal_matrix = model.inverse().transpose();
+        Self {
+            model: model.to_cols_array_2d(),
+            normal_matrix: normal_matrix.to_cols_array_2d(),
+        }
+    }
+}
+
+fn uniform_bind_group_layout(
+    device: &wgpu::Device,
+    label: &str,
+) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some(label),
+        entries: &[wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::VERTEX,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }],
+    })
+}
+
+/// Bind group layout for the group-0 camera uniform.
+pub fn camera_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    uniform_bind_group_layout(device, "Camera Bind Group Layout")
+}
+
+/// Bind group layout for the group-1 per-mesh model uniform.
+pub fn model_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    uniform_bind_group_layout(device, "Model Bind Group Layout")
+}
@@ -0,0 +1,393 @@
+//! Loads glTF/GLB scenes into GPU-ready meshes.
+
+use std::path::Path;
+
+use glam::{Mat4, Vec3};
+use wgpu::util::DeviceExt;
+
+use crate::texture::Texture;
+use crate::VertexInput;
+
+/// Errors that can occur while importing a glTF asset.
+#[derive(Debug)]
+pub enum GltfError {
+    Import(gltf::Error),
+    MissingPositions,
+}
+
+impl std::fmt::Display for GltfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GltfError::Import(err) => write!(f, "failed to import glTF asset: {err}"),
+            GltfError::MissingPositions => write!(f, "primitive is missing a POSITION accessor"),
+        }
+    }
+}
+
+impl std::error::Error for GltfError {}
+
+impl From<gltf::Error> for GltfError {
+    fn from(err: gltf::Error) -> Self {
+        GltfError::Import(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, GltfError>;
+
+/// A single drawable primitive, uploaded as GPU buffers.
+pub struct Mesh {
+    pub vertex_buffer: wgpu::Buffer,
+    pub index_buffer: wgpu::Buffer,
+    pub index_count: u32,
+    pub index_format: wgpu::IndexFormat,
+    /// World-space transform accumulated from this primitive's node ancestry.
+    pub transform: Mat4,
+    /// Index into [`Model::materials`]; always valid, falling back to the
+    /// white default material appended by [`load_materials`].
+    pub material_index: usize,
+}
+
+/// Whether a material is drawn in the opaque depth-prepass/color passes or
+/// sorted back-to-front in the transparent pass.
+///
+/// glTF's `MASK` alpha mode is treated as opaque: we don't implement alpha
+/// cutout testing yet, so masked primitives just render fully opaque.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlendMode {
+    Opaque,
+    Transparent,
+}
+
+/// A glTF material's GPU resources: base-color texture and factor, bound
+/// together as the group-2 material bind group.
+pub struct Material {
+    pub texture: Texture,
+    pub base_color_factor: [f32; 4],
+    pub alpha_mode: BlendMode,
+    pub factor_buffer: wgpu::Buffer,
+    pub bind_group: wgpu::BindGroup,
+}
+
+/// A loaded scene, flattened into a list of meshes in world space.
+pub struct Model {
+    pub meshes: Vec<Mesh>,
+    pub materials: Vec<Material>,
+}
+
+/// Parses a `.gltf`/`.glb` file at `path` and uploads its meshes and
+/// materials to the GPU.
+///
+/// The scene node hierarchy is walked so each primitive's vertices end up in
+/// world space, already transformed by their node's ancestors.
+pub fn load_gltf(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    material_bind_group_layout: &wgpu::BindGroupLayout,
+    path: &Path,
+) -> Result<Model> {
+    let (document, buffers, images) = gltf::import(path)?;
+
+    let materials = load_materials(&document, &images, device, queue, material_bind_group_layout);
+    let fallback_material_index = materials.len() - 1;
+
+    // A glTF file can define multiple scenes; only the default one (falling
+    // back to the first, per the spec) is meant to be shown, so walking
+    // every scene would duplicate geometry for any multi-scene asset.
+    let mut meshes = Vec::new();
+    if let Some(scene) = document.default_scene().or_else(|| document.scenes().next()) {
+        for node in scene.nodes() {
+            walk_node(
+                &node,
+                Mat4::IDENTITY,
+                &buffers,
+                device,
+                fallback_material_index,
+                &mut meshes,
+            )?;
+        }
+    }
+
+    Ok(Model { meshes, materials })
+}
+
+fn load_materials(
+    document: &gltf::Document,
+    images: &[gltf::image::Data],
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    layout: &wgpu::BindGroupLayout,
+) -> Vec<Material> {
+    let mut materials: Vec<Material> = document
+        .materials()
+        .map(|material| {
+            let pbr = material.pbr_metallic_roughness();
+            let texture = match pbr.base_color_texture() {
+                Some(info) => {
+                    let gltf_texture = info.texture();
+                    let image = &images[gltf_texture.source().index()];
+                    Texture::from_gltf_image(
+                        device,
+                        queue,
+                        image,
+                        &gltf_texture.sampler(),
+                        true,
+                        "Base Color Texture",
+                    )
+                }
+                None => Texture::white_1x1(device, queue),
+            };
+
+            let alpha_mode = match material.alpha_mode() {
+                gltf::material::AlphaMode::Blend => BlendMode::Transparent,
+                gltf::material::AlphaMode::Opaque | gltf::material::AlphaMode::Mask => {
+                    BlendMode::Opaque
+                }
+            };
+
+            build_material(device, layout, texture, pbr.base_color_factor(), alpha_mode)
+        })
+        .collect();
+
+    // Fallback material for primitives that reference no material at all,
+    // so every mesh can bind the same group-2 layout.
+    materials.push(build_material(
+        device,
+        layout,
+        Texture::white_1x1(device, queue),
+        [1.0, 1.0, 1.0, 1.0],
+        BlendMode::Opaque,
+    ));
+
+    materials
+}
+
+fn build_material(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    texture: Texture,
+    base_color_factor: [f32; 4],
+    alpha_mode: BlendMode,
+) -> Material {
+    let factor_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Material Factor Buffer"),
+        contents: bytemuck::cast_slice(&[base_color_factor]),
+        usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+    });
+
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Material Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(&texture.view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&texture.sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: factor_buffer.as_entire_binding(),
+            },
+        ],
+    });
+
+    Material {
+        texture,
+        base_color_factor,
+        alpha_mode,
+        factor_buffer,
+        bind_group,
+    }
+}
+
+fn walk_node(
+    node: &gltf::Node,
+    parent_transform: Mat4,
+    buffers: &[gltf::buffer::Data],
+    device: &wgpu::Device,
+    fallback_material_index: usize,
+    meshes: &mut Vec<Mesh>,
+) -> Result<()> {
+    let local_transform = Mat4::from_cols_array_2d(&node.transform().matrix());
+    let world_transform = parent_transform * local_transform;
+
+    if let Some(mesh) = node.mesh() {
+        for primitive in mesh.primitives() {
+            let material_index = primitive
+                .material()
+                .index()
+                .unwrap_or(fallback_material_index);
+            meshes.push(load_primitive(
+                &primitive,
+                world_transform,
+                material_index,
+                buffers,
+                device,
+            )?);
+        }
+    }
+
+    for child in node.children() {
+        walk_node(
+            &child,
+            world_transform,
+            buffers,
+            device,
+            fallback_material_index,
+            meshes,
+        )?;
+    }
+
+    Ok(())
+}
+
+fn load_primitive(
+    primitive: &gltf::Primitive,
+    transform: Mat4,
+    material_index: usize,
+    buffers: &[gltf::buffer::Data],
+    device: &wgpu::Device,
+) -> Result<Mesh> {
+    let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+
+    let positions: Vec<[f32; 3]> = reader
+        .read_positions()
+        .ok_or(GltfError::MissingPositions)?
+        .collect();
+
+    let uvs: Vec<[f32; 2]> = reader
+        .read_tex_coords(0)
+        .map(|iter| iter.into_f32().collect())
+        .unwrap_or_else(|| vec![[0.0, 0.0]; positions.len()]);
+
+    // `indices` is optional in glTF: a non-indexed primitive is drawn using
+    // its vertices in storage order, so synthesize the equivalent sequential
+    // index buffer rather than treating this as a load failure.
+    let indices: Vec<u32> = match reader.read_indices() {
+        Some(indices) => indices.into_u32().collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+
+    let normals: Vec<[f32; 3]> = match reader.read_normals() {
+        Some(iter) => iter.collect(),
+        None => generate_flat_normals(&positions, &indices),
+    };
+
+    // Vertices stay in local space; the camera/model uniforms apply the
+    // world transform on the GPU so it can be re-used for skinning and
+    // instancing later.
+    let vertices: Vec<VertexInput> = (0..positions.len())
+        .map(|i| VertexInput {
+            position: positions[i],
+            normal: normals[i],
+            uv: uvs[i],
+        })
+        .collect();
+
+    let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Mesh Vertex Buffer"),
+        contents: bytemuck::cast_slice(&vertices),
+        usage: wgpu::BufferUsages::VERTEX,
+    });
+
+    let index_count = indices.len() as u32;
+    let index_format = index_format_for(&indices);
+    let index_buffer = match index_format {
+        wgpu::IndexFormat::Uint16 => {
+            let indices: Vec<u16> = indices.iter().map(|&i| i as u16).collect();
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Mesh Index Buffer"),
+                contents: bytemuck::cast_slice(&indices),
+                usage: wgpu::BufferUsages::INDEX,
+            })
+        }
+        wgpu::IndexFormat::Uint32 => device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mesh Index Buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        }),
+    };
+
+    Ok(Mesh {
+        vertex_buffer,
+        index_buffer,
+        index_count,
+        index_format,
+        transform,
+        material_index,
+    })
+}
+
+/// The smallest index format that can represent every value in `indices`,
+/// so small meshes get a half-size index buffer instead of always paying
+/// for `Uint32`.
+fn index_format_for(indices: &[u32]) -> wgpu::IndexFormat {
+    if indices.iter().all(|&i| i <= u16::MAX as u32) {
+        wgpu::IndexFormat::Uint16
+    } else {
+        wgpu::IndexFormat::Uint32
+    }
+}
+
+/// Averages per-face cross-product normals onto each vertex for primitives
+/// that don't ship a NORMAL accessor.
+fn generate_flat_normals(positions: &[[f32; 3]], indices: &[u32]) -> Vec<[f32; 3]> {
+    let mut normals = vec![Vec3::ZERO; positions.len()];
+
+    for tri in indices.chunks_exact(3) {
+        let (a, b, c) = (tri[0] as usize, tri[1] as usize, tri[2] as usize);
+        let pa = Vec3::from(positions[a]);
+        let pb = Vec3::from(positions[b]);
+        let pc = Vec3::from(positions[c]);
+        let face_normal = (pb - pa).cross(pc - pa);
+        normals[a] += face_normal;
+        normals[b] += face_normal;
+        normals[c] += face_normal;
+    }
+
+    normals
+        .into_iter()
+        .map(|n| n.normalize_or_zero().into())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flat_normal_points_away_from_counter_clockwise_winding() {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let indices = [0, 1, 2];
+
+        let normals = generate_flat_normals(&positions, &indices);
+
+        for normal in normals {
+            assert!(Vec3::from(normal).abs_diff_eq(Vec3::Z, 1e-6));
+        }
+    }
+
+    #[test]
+    fn flat_normal_is_zero_for_a_vertex_touched_by_no_face() {
+        let positions = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [5.0, 5.0, 5.0]];
+        let indices = [0, 1, 2];
+
+        let normals = generate_flat_normals(&positions, &indices);
+
+        assert_eq!(normals[3], [0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn index_format_stays_16_bit_up_to_u16_max() {
+        let indices = [0, 1, u16::MAX as u32];
+        assert_eq!(index_format_for(&indices), wgpu::IndexFormat::Uint16);
+    }
+
+    #[test]
+    fn index_format_widens_to_32_bit_past_u16_max() {
+        let indices = [0, 1, u16::MAX as u32 + 1];
+        assert_eq!(index_format_for(&indices), wgpu::IndexFormat::Uint32);
+    }
+}
@@ -1,8 +1,7 @@
-use std::{borrow::Cow, sync::Arc};
+use std::sync::Arc;
 
 use tokio::runtime::Runtime;
 use tracing_subscriber::EnvFilter;
-use wgpu::util::DeviceExt;
 use winit::{
     application::ApplicationHandler,
     event::WindowEvent,
@@ -10,17 +9,27 @@ use winit::{
     window::{Window, WindowAttributes, WindowId},
 };
 
+mod camera;
+mod gltf;
+mod postprocess;
+mod renderer;
+mod texture;
+
+use renderer::Renderer;
+
 #[repr(C)]
 #[derive(Debug, PartialEq, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct VertexInput {
-    pub position: [f32; 4],
-    pub color: [f32; 4],
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub uv: [f32; 2],
 }
 
 impl VertexInput {
-    const ATTRIBUTES: [wgpu::VertexAttribute; 2] = wgpu::vertex_attr_array![
-        0 => Float32x4,
-        1 => Float32x4,
+    const ATTRIBUTES: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![
+        0 => Float32x3,
+        1 => Float32x3,
+        2 => Float32x2,
     ];
 
     fn desc<'a>() -> wgpu::VertexBufferLayout<'a> {
@@ -32,17 +41,17 @@ impl VertexInput {
     }
 }
 
+/// Everything that needs a live window and `Surface`: the swapchain and the
+/// window-sized attachments (depth, MSAA) that must be recreated on resize.
+/// Scene rendering itself is delegated to [`Renderer`], which knows nothing
+/// about windows or surfaces.
 struct State {
     window: Arc<dyn Window>,
-    device: wgpu::Device,
-    queue: wgpu::Queue,
     size: winit::dpi::PhysicalSize<u32>,
     surface: wgpu::Surface<'static>,
-    surface_format: wgpu::TextureFormat,
-    render_pipeline: wgpu::RenderPipeline,
-    data_buffer: wgpu::Buffer,
-    depth_texture_format: wgpu::TextureFormat,
+    renderer: Renderer,
     depth_texture: Option<wgpu::Texture>,
+    multisampled_texture: Option<wgpu::Texture>,
 }
 
 impl State {
@@ -74,80 +83,20 @@ impl State {
             .await
             .unwrap();
 
-        // Load the shaders from disk
-        let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/shader.wgsl"));
-        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: None,
-            bind_group_layouts: &[],
-            push_constant_ranges: &[],
-        });
-
         let swapchain_capabilities = surface.get_capabilities(&adapter);
         let swapchain_format = swapchain_capabilities.formats[0];
 
-        let depth_texture_format = wgpu::TextureFormat::Depth24PlusStencil8;
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: None,
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: Some("vs_main"),
-                buffers: &[VertexInput::desc()],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: Some("fs_main"),
-                compilation_options: Default::default(),
-                targets: &[Some(swapchain_format.into())],
-            }),
-            primitive: wgpu::PrimitiveState::default(),
-            depth_stencil: Some(wgpu::DepthStencilState {
-                format: depth_texture_format,
-                depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
-                stencil: wgpu::StencilState::default(),
-                bias: wgpu::DepthBiasState::default(),
-            }),
-            // depth_stencil: None,
-            multisample: wgpu::MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
-
         let size = window.surface_size();
 
-        let data_buf = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(&[
-                VertexInput {
-                    position: [1.0, -1.0, 0.0, 1.0],
-                    color: [1.0, 0.0, 0.0, 1.0],
-                },
-                VertexInput {
-                    position: [-1.0, -1.0, 0.0, 1.0],
-                    color: [0.0, 1.0, 0.0, 1.0],
-                },
-                VertexInput {
-                    position: [0.0, 1.0, 0.0, 1.0],
-                    color: [0.0, 0.0, 1.0, 1.0],
-                },
-            ]),
-            usage: wgpu::BufferUsages::VERTEX,
-        });
+        let renderer = Renderer::new(&adapter, device, queue, swapchain_format, size.width, size.height).await;
 
         let mut state = State {
             window,
-            device,
-            queue,
             size,
             surface,
-            surface_format: swapchain_format,
-            render_pipeline,
-            data_buffer: data_buf,
-            depth_texture_format,
+            renderer,
             depth_texture: None,
+            multisampled_texture: None,
         };
 
         // Configure surface for the first time
@@ -164,12 +113,12 @@ impl State {
         println!("Configuring surface");
         println!("Surface size: {:?}", self.size);
         self.surface.configure(
-            &self.device,
+            &self.renderer.device,
             &wgpu::SurfaceConfiguration {
                 usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-                format: self.surface_format,
+                format: self.renderer.surface_format,
                 // Request compatibility with the sRGB-format texture view we‘re going to create later.
-                view_formats: vec![self.surface_format.add_srgb_suffix()],
+                view_formats: vec![self.renderer.surface_format.add_srgb_suffix()],
                 alpha_mode: wgpu::CompositeAlphaMode::PreMultiplied,
                 width: self.size.width,
                 height: self.size.height,
@@ -178,7 +127,7 @@ impl State {
             },
         );
 
-        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+        let texture = self.renderer.device.create_texture(&wgpu::TextureDescriptor {
             label: Some("Texture"),
             size: wgpu::Extent3d {
                 width: self.size.width,
@@ -186,13 +135,32 @@ impl State {
                 depth_or_array_layers: 1,
             },
             mip_level_count: 1,
-            sample_count: 1,
+            sample_count: self.renderer.sample_count,
             dimension: wgpu::TextureDimension::D2,
-            format: self.depth_texture_format,
+            format: self.renderer.depth_texture_format,
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             view_formats: &[],
         });
         self.depth_texture = Some(texture);
+
+        self.multisampled_texture = if self.renderer.sample_count > 1 {
+            Some(self.renderer.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Multisampled Color Texture"),
+                size: wgpu::Extent3d {
+                    width: self.size.width,
+                    height: self.size.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: self.renderer.sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.renderer.surface_format.add_srgb_suffix(),
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            }))
+        } else {
+            None
+        };
     }
 
     fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
@@ -200,6 +168,7 @@ impl State {
 
         // reconfigure the surface
         self.configure_surface();
+        self.renderer.resize(new_size.width, new_size.height);
     }
 
     fn render(&mut self) {
@@ -213,56 +182,37 @@ impl State {
             .create_view(&wgpu::TextureViewDescriptor {
                 // Without add_srgb_suffix() the image we will be working with
                 // might not be "gamma correct".
-                format: Some(self.surface_format.add_srgb_suffix()),
+                format: Some(self.renderer.surface_format.add_srgb_suffix()),
                 ..Default::default()
             });
 
-        // Renders a GREEN screen
-        let mut encoder = self.device.create_command_encoder(&Default::default());
-        {
-            // Create the renderpass which will clear the screen.
-            let mut renderpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: None,
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &texture_view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.3,
-                            g: 0.3,
-                            b: 0.3,
-                            a: 1.0,
-                        }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-                    view: &self
-                        .depth_texture
-                        .as_ref()
-                        .unwrap()
-                        .create_view(&Default::default()),
-                    depth_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(1.0),
-                        store: wgpu::StoreOp::Store,
-                    }),
-                    stencil_ops: Some(wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(0),
-                        store: wgpu::StoreOp::Store,
-                    }),
-                }),
-                // timestamp_writes: None,
-                // occlusion_query_set: None,
-                ..Default::default()
-            });
+        // The scene renders into the post-process chain's input texture
+        // rather than the surface view directly, so the filter stack has
+        // something to read for its first pass.
+        let scene_target = self.renderer.post_process_input_view();
+
+        let multisampled_view = self
+            .multisampled_texture
+            .as_ref()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        let (color_view, resolve_target) = match &multisampled_view {
+            Some(msaa_view) => (msaa_view, Some(scene_target)),
+            None => (scene_target, None),
+        };
 
-            renderpass.set_pipeline(&self.render_pipeline);
-            renderpass.set_vertex_buffer(0, self.data_buffer.slice(..));
-            renderpass.draw(0..3, 0..1);
-        }
+        let depth_view = self
+            .depth_texture
+            .as_ref()
+            .unwrap()
+            .create_view(&Default::default());
+
+        let mut encoder = self.renderer.device.create_command_encoder(&Default::default());
+        self.renderer
+            .draw_scene(&mut encoder, color_view, resolve_target, &depth_view);
+        self.renderer.present_post_process(&mut encoder, &texture_view);
 
         // Submit the command in the queue to execute
-        self.queue.submit([encoder.finish()]);
+        self.renderer.queue.submit([encoder.finish()]);
         self.window.pre_present_notify();
         surface_texture.present();
     }
@@ -0,0 +1,317 @@
+//! A configurable stack of fullscreen post-process passes (tonemapping,
+//! FXAA, color grading, ...) applied to the rendered scene before it's
+//! presented.
+//!
+//! [`FilterChain`] owns two same-sized color textures and ping-pongs
+//! between them: the scene is rendered into [`FilterChain::input_view`],
+//! then each pass samples the previous texture and writes the next one,
+//! with the final pass targeting the caller's output view (typically the
+//! swapchain) instead of a ping-pong texture.
+
+use wgpu::util::DeviceExt;
+
+/// Per-pass uniform: output resolution (for neighbour-sampling effects like
+/// FXAA) and a running clock (for animated effects like color grading).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct PostProcessUniform {
+    resolution: [f32; 2],
+    time: f32,
+    frame: u32,
+}
+
+struct Pass {
+    pipeline: wgpu::RenderPipeline,
+}
+
+pub struct FilterChain {
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+    views: [wgpu::TextureView; 2],
+    sampler: wgpu::Sampler,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    texture_bind_groups: [wgpu::BindGroup; 2],
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+    passes: Vec<Pass>,
+}
+
+impl FilterChain {
+    /// Builds a filter chain from an ordered list of fragment-shader WGSL
+    /// sources, each paired with the shared `fullscreen.wgsl` vertex stage.
+    pub fn new(
+        device: &wgpu::Device,
+        format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        pass_shader_sources: &[&str],
+    ) -> Self {
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Post Process Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        let texture_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Post Process Texture Bind Group Layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: false,
+                        },
+                        count: None,
+                    },
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 1,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                        count: None,
+                    },
+                ],
+            });
+
+        let uniform_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Post Process Uniform Bind Group Layout"),
+                entries: &[wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }],
+            });
+
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Post Process Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[PostProcessUniform {
+                resolution: [width as f32, height as f32],
+                time: 0.0,
+                frame: 0,
+            }]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Post Process Uniform Bind Group"),
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let fullscreen_shader =
+            device.create_shader_module(wgpu::include_wgsl!("shaders/fullscreen.wgsl"));
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Post Process Pipeline Layout"),
+            bind_group_layouts: &[&texture_bind_group_layout, &uniform_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let passes = pass_shader_sources
+            .iter()
+            .map(|source| {
+                let fragment_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                    label: Some("Post Process Fragment Shader"),
+                    source: wgpu::ShaderSource::Wgsl((*source).into()),
+                });
+
+                let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                    label: Some("Post Process Pipeline"),
+                    layout: Some(&pipeline_layout),
+                    vertex: wgpu::VertexState {
+                        module: &fullscreen_shader,
+                        entry_point: Some("vs_main"),
+                        buffers: &[],
+                        compilation_options: Default::default(),
+                    },
+                    fragment: Some(wgpu::FragmentState {
+                        module: &fragment_shader,
+                        entry_point: Some("fs_main"),
+                        compilation_options: Default::default(),
+                        targets: &[Some(format.into())],
+                    }),
+                    primitive: wgpu::PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: wgpu::MultisampleState::default(),
+                    multiview: None,
+                    cache: None,
+                });
+
+                Pass { pipeline }
+            })
+            .collect();
+
+        let views = create_ping_pong_textures(device, format, width, height);
+        let texture_bind_groups = [
+            create_texture_bind_group(device, &texture_bind_group_layout, &views[0], &sampler),
+            create_texture_bind_group(device, &texture_bind_group_layout, &views[1], &sampler),
+        ];
+
+        Self {
+            format,
+            width,
+            height,
+            views,
+            sampler,
+            texture_bind_group_layout,
+            texture_bind_groups,
+            uniform_buffer,
+            uniform_bind_group,
+            passes,
+        }
+    }
+
+    /// Recreates the ping-pong textures at the new size. Must be called
+    /// whenever the surface is resized, before the next `render`.
+    pub fn resize(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+
+        let views = create_ping_pong_textures(device, self.format, width, height);
+        self.texture_bind_groups = [
+            create_texture_bind_group(
+                device,
+                &self.texture_bind_group_layout,
+                &views[0],
+                &self.sampler,
+            ),
+            create_texture_bind_group(
+                device,
+                &self.texture_bind_group_layout,
+                &views[1],
+                &self.sampler,
+            ),
+        ];
+        self.views = views;
+    }
+
+    /// The texture the scene should render into, in place of the swapchain
+    /// view, so the chain has something to read for its first pass.
+    pub fn input_view(&self) -> &wgpu::TextureView {
+        &self.views[0]
+    }
+
+    /// Runs every pass in order, reading from the scene's render target and
+    /// writing intermediate results until the last pass lands on
+    /// `output_view`.
+    pub fn render(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        queue: &wgpu::Queue,
+        output_view: &wgpu::TextureView,
+        time: f32,
+        frame: u32,
+    ) {
+        queue.write_buffer(
+            &self.uniform_buffer,
+            0,
+            bytemuck::cast_slice(&[PostProcessUniform {
+                resolution: [self.width as f32, self.height as f32],
+                time,
+                frame,
+            }]),
+        );
+
+        if self.passes.is_empty() {
+            return;
+        }
+
+        let mut read_index = 0;
+        for (i, pass) in self.passes.iter().enumerate() {
+            let is_last = i == self.passes.len() - 1;
+            let target_view = if is_last {
+                output_view
+            } else {
+                &self.views[1 - read_index]
+            };
+
+            let mut renderpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Post Process Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: target_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                ..Default::default()
+            });
+
+            renderpass.set_pipeline(&pass.pipeline);
+            renderpass.set_bind_group(0, &self.texture_bind_groups[read_index], &[]);
+            renderpass.set_bind_group(1, &self.uniform_bind_group, &[]);
+            renderpass.draw(0..3, 0..1);
+
+            read_index = 1 - read_index;
+        }
+    }
+}
+
+fn create_ping_pong_textures(
+    device: &wgpu::Device,
+    format: wgpu::TextureFormat,
+    width: u32,
+    height: u32,
+) -> [wgpu::TextureView; 2] {
+    let make_view = |label: &str| {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        texture.create_view(&wgpu::TextureViewDescriptor::default())
+    };
+
+    [
+        make_view("Post Process Texture A"),
+        make_view("Post Process Texture B"),
+    ]
+}
+
+fn create_texture_bind_group(
+    device: &wgpu::Device,
+    layout: &wgpu::BindGroupLayout,
+    view: &wgpu::TextureView,
+    sampler: &wgpu::Sampler,
+) -> wgpu::BindGroup {
+    device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("Post Process Texture Bind Group"),
+        layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(view),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(sampler),
+            },
+        ],
+    })
+}
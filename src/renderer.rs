@@ -0,0 +1,685 @@
+//! The GPU-resident scene renderer: pipelines, the loaded glTF model,
+//! camera, and post-process chain.
+//!
+//! Deliberately independent of any window or `wgpu::Surface` — the only
+//! thing a `Surface` is used for elsewhere is picking a compatible
+//! `surface_format` and presenting the final frame, neither of which
+//! `Renderer` itself needs. That keeps [`Renderer::render_to_texture`]
+//! (and [`Renderer::new_headless`], used by tests) fully driveable without
+//! a live display.
+
+use std::path::Path;
+
+use wgpu::util::DeviceExt;
+
+use crate::camera::{self, Camera, CameraUniform, ModelUniform};
+use crate::gltf::{self, BlendMode, Model};
+use crate::postprocess::FilterChain;
+use crate::texture;
+use crate::VertexInput;
+
+/// Path to the glTF/GLB asset loaded on startup.
+pub const MODEL_PATH: &str = "assets/model.glb";
+
+/// Preferred MSAA sample count; falls back to 1 if the adapter can't
+/// multisample the chosen surface format.
+pub const DEFAULT_SAMPLE_COUNT: u32 = 4;
+
+/// The post-process stack applied to the scene before it's presented, run
+/// in this order.
+const POSTPROCESS_SHADERS: &[&str] = &[
+    include_str!("shaders/postprocess/tonemap.wgsl"),
+    include_str!("shaders/postprocess/fxaa.wgsl"),
+    include_str!("shaders/postprocess/color_grade.wgsl"),
+];
+
+/// Clamps `desired` down to 1 if `format` doesn't support multisampling at
+/// that sample count on `adapter`.
+pub fn supported_sample_count(
+    adapter: &wgpu::Adapter,
+    format: wgpu::TextureFormat,
+    desired: u32,
+) -> u32 {
+    let flags = adapter.get_texture_format_features(format).flags;
+    let supported = match desired {
+        1 => true,
+        2 => flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X2),
+        4 => flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X4),
+        8 => flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X8),
+        16 => flags.contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X16),
+        _ => false,
+    };
+    if supported { desired } else { 1 }
+}
+
+/// Render phases, executed in this order every frame: an opaque depth
+/// prepass, the opaque color pass (which reuses that depth), then
+/// back-to-front transparent draws. Kept as an explicit enum so a future
+/// pass (e.g. shadows) has an obvious place to slot in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    DepthPrepass,
+    OpaqueColor,
+    Transparent,
+}
+
+/// Owns the pipelines, loaded scene, camera, and post-process chain needed
+/// to render a frame into any color view of `surface_format` at a given
+/// size. Holding no `Surface` or window reference, it's equally usable for
+/// on-screen presentation (driven by `State`) and headless rendering (see
+/// [`Renderer::render_to_texture`]).
+pub struct Renderer {
+    pub device: wgpu::Device,
+    pub queue: wgpu::Queue,
+    pub surface_format: wgpu::TextureFormat,
+    depth_prepass_pipeline: wgpu::RenderPipeline,
+    opaque_color_pipeline: wgpu::RenderPipeline,
+    transparent_pipeline: wgpu::RenderPipeline,
+    model: Model,
+    mesh_bind_groups: Vec<wgpu::BindGroup>,
+    camera: Camera,
+    camera_uniform: CameraUniform,
+    camera_buffer: wgpu::Buffer,
+    camera_bind_group: wgpu::BindGroup,
+    pub depth_texture_format: wgpu::TextureFormat,
+    pub sample_count: u32,
+    post_process: FilterChain,
+    start_time: std::time::Instant,
+    frame_count: u32,
+}
+
+impl Renderer {
+    /// Builds the render pipelines, loads [`MODEL_PATH`], and sets up the
+    /// post-process chain for a `width`x`height` target in `surface_format`.
+    pub async fn new(
+        adapter: &wgpu::Adapter,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        surface_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Renderer {
+        // Load the shaders from disk
+        let shader = device.create_shader_module(wgpu::include_wgsl!("shaders/shader.wgsl"));
+
+        let camera_bind_group_layout = camera::camera_bind_group_layout(&device);
+        let model_bind_group_layout = camera::model_bind_group_layout(&device);
+        let material_bind_group_layout = texture::material_bind_group_layout(&device);
+
+        // The depth prepass only needs camera + model transforms: it has no
+        // fragment stage, so it never touches the material group.
+        let depth_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Depth Prepass Pipeline Layout"),
+            bind_group_layouts: &[&camera_bind_group_layout, &model_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let color_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Color Pipeline Layout"),
+            bind_group_layouts: &[
+                &camera_bind_group_layout,
+                &model_bind_group_layout,
+                &material_bind_group_layout,
+            ],
+            push_constant_ranges: &[],
+        });
+
+        let depth_texture_format = wgpu::TextureFormat::Depth24PlusStencil8;
+        let sample_count = supported_sample_count(adapter, surface_format, DEFAULT_SAMPLE_COUNT);
+        let multisample = wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        };
+
+        // Opaque depth prepass: populates the depth buffer with no color
+        // target, so the color pass below only shades visible fragments.
+        let depth_prepass_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Depth Prepass Pipeline"),
+            layout: Some(&depth_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_depth_prepass"),
+                buffers: &[VertexInput::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: None,
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_texture_format,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample,
+            multiview: None,
+            cache: None,
+        });
+
+        // Opaque color pass: the prepass already wrote depth, so this only
+        // shades fragments that are exactly at the visible depth. The
+        // target format is sRGB-suffixed to match the views it's actually
+        // drawn into (the MSAA texture and the post-process input view are
+        // both created with `surface_format.add_srgb_suffix()`), since wgpu
+        // requires an exact format match between pipeline and attachment.
+        let opaque_color_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Opaque Color Pipeline"),
+            layout: Some(&color_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[VertexInput::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(surface_format.add_srgb_suffix().into())],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_texture_format,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::Equal,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample,
+            multiview: None,
+            cache: None,
+        });
+
+        // Transparent pass: drawn back-to-front with alpha blending and no
+        // depth writes, tested (but not written) against the opaque depth.
+        let transparent_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Transparent Pipeline"),
+            layout: Some(&color_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[VertexInput::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                compilation_options: Default::default(),
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format.add_srgb_suffix(),
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: depth_texture_format,
+                depth_write_enabled: false,
+                depth_compare: wgpu::CompareFunction::LessEqual,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample,
+            multiview: None,
+            cache: None,
+        });
+
+        let model = gltf::load_gltf(
+            &device,
+            &queue,
+            &material_bind_group_layout,
+            Path::new(MODEL_PATH),
+        )
+        .expect("failed to load glTF model");
+
+        let camera = Camera::new(width as f32 / height.max(1) as f32);
+        let mut camera_uniform = CameraUniform::new();
+        camera_uniform.update(&camera);
+
+        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Camera Buffer"),
+            contents: bytemuck::cast_slice(&[camera_uniform]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Camera Bind Group"),
+            layout: &camera_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: camera_buffer.as_entire_binding(),
+            }],
+        });
+
+        let mesh_bind_groups = model
+            .meshes
+            .iter()
+            .map(|mesh| {
+                let model_uniform = ModelUniform::new(mesh.transform);
+                let model_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Model Buffer"),
+                    contents: bytemuck::cast_slice(&[model_uniform]),
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                });
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Model Bind Group"),
+                    layout: &model_bind_group_layout,
+                    entries: &[wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: model_buffer.as_entire_binding(),
+                    }],
+                })
+            })
+            .collect();
+
+        let post_process = FilterChain::new(
+            &device,
+            surface_format.add_srgb_suffix(),
+            width,
+            height,
+            POSTPROCESS_SHADERS,
+        );
+
+        Renderer {
+            device,
+            queue,
+            surface_format,
+            depth_prepass_pipeline,
+            opaque_color_pipeline,
+            transparent_pipeline,
+            model,
+            mesh_bind_groups,
+            camera,
+            camera_uniform,
+            camera_buffer,
+            camera_bind_group,
+            depth_texture_format,
+            sample_count,
+            post_process,
+            start_time: std::time::Instant::now(),
+            frame_count: 0,
+        }
+    }
+
+    /// Creates a `Renderer` with its own headless device/adapter/queue and
+    /// no `Surface`, for tests and other non-interactive uses (e.g.
+    /// generating a model thumbnail from a CLI tool).
+    pub async fn new_headless(width: u32, height: u32) -> Renderer {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await
+            .expect("no wgpu adapter available for headless rendering");
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: Some("Headless Device"),
+                required_features: wgpu::Features::empty(),
+                required_limits: wgpu::Limits::default(),
+                memory_hints: wgpu::MemoryHints::MemoryUsage,
+                trace: wgpu::Trace::Off,
+            })
+            .await
+            .expect("failed to request headless device");
+
+        // Mirrors the windowed path: `State::new` picks a bare (non-sRGB)
+        // swapchain format from `surface.get_capabilities`, and `Renderer`
+        // sRGB-suffixes it internally wherever it needs to. Passing an
+        // already-sRGB format here would make `add_srgb_suffix()` a no-op
+        // and hide any pipeline/attachment format mismatch that only shows
+        // up when the suffixing actually changes the format.
+        Renderer::new(
+            &adapter,
+            device,
+            queue,
+            wgpu::TextureFormat::Rgba8Unorm,
+            width,
+            height,
+        )
+        .await
+    }
+
+    /// Updates the camera aspect ratio and resizes the post-process chain
+    /// to match a new on-screen surface size.
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.camera.aspect = width as f32 / height.max(1) as f32;
+        self.camera_uniform.update(&self.camera);
+        self.queue.write_buffer(
+            &self.camera_buffer,
+            0,
+            bytemuck::cast_slice(&[self.camera_uniform]),
+        );
+        self.post_process.resize(&self.device, width, height);
+    }
+
+    /// The texture the scene should render into, in place of the final
+    /// output view, so the post-process chain has something to read for
+    /// its first pass.
+    pub fn post_process_input_view(&self) -> &wgpu::TextureView {
+        self.post_process.input_view()
+    }
+
+    /// Runs the post-process chain, reading from [`Self::post_process_input_view`]
+    /// and writing the final image to `output_view`.
+    pub fn present_post_process(
+        &mut self,
+        encoder: &mut wgpu::CommandEncoder,
+        output_view: &wgpu::TextureView,
+    ) {
+        self.frame_count = self.frame_count.wrapping_add(1);
+        let time = self.start_time.elapsed().as_secs_f32();
+        self.post_process
+            .render(encoder, &self.queue, output_view, time, self.frame_count);
+    }
+
+    fn pipeline_for(&self, phase: Phase) -> &wgpu::RenderPipeline {
+        match phase {
+            Phase::DepthPrepass => &self.depth_prepass_pipeline,
+            Phase::OpaqueColor => &self.opaque_color_pipeline,
+            Phase::Transparent => &self.transparent_pipeline,
+        }
+    }
+
+    /// Meshes whose material isn't alpha-blended, in no particular order.
+    fn opaque_meshes(&self) -> impl Iterator<Item = (&gltf::Mesh, &wgpu::BindGroup)> {
+        self.model
+            .meshes
+            .iter()
+            .zip(&self.mesh_bind_groups)
+            .filter(|(mesh, _)| {
+                self.model.materials[mesh.material_index].alpha_mode == BlendMode::Opaque
+            })
+    }
+
+    /// Alpha-blended meshes sorted back-to-front by distance from the
+    /// camera to each mesh's origin, so blending composites correctly.
+    fn transparent_meshes_back_to_front(&self) -> Vec<(&gltf::Mesh, &wgpu::BindGroup)> {
+        let mut meshes: Vec<_> = self
+            .model
+            .meshes
+            .iter()
+            .zip(&self.mesh_bind_groups)
+            .filter(|(mesh, _)| {
+                self.model.materials[mesh.material_index].alpha_mode == BlendMode::Transparent
+            })
+            .collect();
+
+        meshes.sort_by(|(a, _), (b, _)| {
+            let distance_a = self.distance_to_camera(a);
+            let distance_b = self.distance_to_camera(b);
+            distance_b
+                .partial_cmp(&distance_a)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        meshes
+    }
+
+    fn distance_to_camera(&self, mesh: &gltf::Mesh) -> f32 {
+        let origin = mesh.transform.transform_point3(glam::Vec3::ZERO);
+        origin.distance_squared(self.camera.position)
+    }
+
+    /// Opaque depth prepass: populates `depth_view` with no color target.
+    fn draw_depth_prepass(&self, encoder: &mut wgpu::CommandEncoder, depth_view: &wgpu::TextureView) {
+        let mut renderpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Depth Prepass"),
+            color_attachments: &[],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0),
+                    store: wgpu::StoreOp::Store,
+                }),
+            }),
+            ..Default::default()
+        });
+
+        renderpass.set_pipeline(self.pipeline_for(Phase::DepthPrepass));
+        renderpass.set_bind_group(0, &self.camera_bind_group, &[]);
+        for (mesh, model_bind_group) in self.opaque_meshes() {
+            renderpass.set_bind_group(1, model_bind_group, &[]);
+            renderpass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            renderpass.set_index_buffer(mesh.index_buffer.slice(..), mesh.index_format);
+            renderpass.draw_indexed(0..mesh.index_count, 0, 0..1);
+        }
+    }
+
+    /// Opaque color pass (depth already populated by the prepass) followed
+    /// by the back-to-front transparent pass, sharing the same attachments.
+    fn draw_color_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
+        depth_view: &wgpu::TextureView,
+    ) {
+        let mut renderpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Color Pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: color_view,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color {
+                        r: 0.3,
+                        g: 0.3,
+                        b: 0.3,
+                        a: 1.0,
+                    }),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: wgpu::StoreOp::Store,
+                }),
+            }),
+            ..Default::default()
+        });
+
+        renderpass.set_bind_group(0, &self.camera_bind_group, &[]);
+
+        renderpass.set_pipeline(self.pipeline_for(Phase::OpaqueColor));
+        for (mesh, model_bind_group) in self.opaque_meshes() {
+            renderpass.set_bind_group(1, model_bind_group, &[]);
+            renderpass.set_bind_group(2, &self.model.materials[mesh.material_index].bind_group, &[]);
+            renderpass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            renderpass.set_index_buffer(mesh.index_buffer.slice(..), mesh.index_format);
+            renderpass.draw_indexed(0..mesh.index_count, 0, 0..1);
+        }
+
+        renderpass.set_pipeline(self.pipeline_for(Phase::Transparent));
+        for (mesh, model_bind_group) in self.transparent_meshes_back_to_front() {
+            renderpass.set_bind_group(1, model_bind_group, &[]);
+            renderpass.set_bind_group(2, &self.model.materials[mesh.material_index].bind_group, &[]);
+            renderpass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+            renderpass.set_index_buffer(mesh.index_buffer.slice(..), mesh.index_format);
+            renderpass.draw_indexed(0..mesh.index_count, 0, 0..1);
+        }
+    }
+
+    /// Runs the full phase pipeline (depth prepass, then opaque + transparent
+    /// color) shared by the on-screen rendering path and
+    /// [`Self::render_to_texture`].
+    pub fn draw_scene(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        color_view: &wgpu::TextureView,
+        resolve_target: Option<&wgpu::TextureView>,
+        depth_view: &wgpu::TextureView,
+    ) {
+        self.draw_depth_prepass(encoder, depth_view);
+        self.draw_color_pass(encoder, color_view, resolve_target, depth_view);
+    }
+
+    /// Renders the scene into an offscreen texture with no `Surface`
+    /// involved, then reads it back into a CPU-side image. Useful for
+    /// thumbnailing a loaded model or for snapshot tests in CI.
+    ///
+    /// Runs its own post-process chain sized to `width`/`height` (rather
+    /// than reusing `self`'s, which tracks the on-screen surface size) so
+    /// the thumbnail matches what's shown on screen, tonemapping/AA/grading
+    /// included.
+    pub fn render_to_texture(&mut self, width: u32, height: u32) -> image::RgbaImage {
+        let format = self.surface_format.add_srgb_suffix();
+        let extent = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+
+        let target_texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Color Texture"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let target_view = target_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let post_process = FilterChain::new(&self.device, format, width, height, POSTPROCESS_SHADERS);
+
+        let depth_texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen Depth Texture"),
+            size: extent,
+            mip_level_count: 1,
+            sample_count: self.sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: self.depth_texture_format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let multisampled_texture = if self.sample_count > 1 {
+            Some(self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Offscreen Multisampled Color Texture"),
+                size: extent,
+                mip_level_count: 1,
+                sample_count: self.sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            }))
+        } else {
+            None
+        };
+        let scene_target = post_process.input_view();
+        let multisampled_view = multisampled_texture
+            .as_ref()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        let (color_view, resolve_target) = match &multisampled_view {
+            Some(msaa_view) => (msaa_view, Some(scene_target)),
+            None => (scene_target, None),
+        };
+
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        self.draw_scene(&mut encoder, color_view, resolve_target, &depth_view);
+
+        let time = self.start_time.elapsed().as_secs_f32();
+        post_process.render(&mut encoder, &self.queue, &target_view, time, self.frame_count);
+
+        let bytes_per_pixel = 4u32;
+        let unpadded_bytes_per_row = width * bytes_per_pixel;
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = unpadded_bytes_per_row.div_ceil(align) * align;
+
+        let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback Buffer"),
+            size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &target_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            extent,
+        );
+
+        self.queue.submit([encoder.finish()]);
+
+        let buffer_slice = readback_buffer.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        buffer_slice.map_async(wgpu::MapMode::Read, move |result| {
+            tx.send(result).expect("readback channel closed");
+        });
+        self.device
+            .poll(wgpu::PollType::Wait)
+            .expect("failed to poll device for readback");
+        rx.recv()
+            .expect("readback never completed")
+            .expect("failed to map readback buffer");
+
+        let is_bgra = matches!(
+            format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        );
+
+        let mapped = buffer_slice.get_mapped_range();
+        let mut pixels = Vec::with_capacity((width * height * bytes_per_pixel) as usize);
+        for row in 0..height {
+            let start = (row * padded_bytes_per_row) as usize;
+            let row_bytes = &mapped[start..start + unpadded_bytes_per_row as usize];
+            if is_bgra {
+                pixels.extend(row_bytes.chunks_exact(4).flat_map(|p| [p[2], p[1], p[0], p[3]]));
+            } else {
+                pixels.extend_from_slice(row_bytes);
+            }
+        }
+        drop(mapped);
+        readback_buffer.unmap();
+
+        image::RgbaImage::from_raw(width, height, pixels)
+            .expect("readback buffer size didn't match the requested image dimensions")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Exercises the headless path end-to-end against a real (software or
+    /// hardware) adapter: loads the model, runs the full phase pipeline and
+    /// post-process chain, and reads the result back to the CPU. Requires a
+    /// wgpu-compatible adapter to be available in the test environment.
+    #[test]
+    fn render_to_texture_produces_an_opaque_image_of_the_requested_size() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut renderer = runtime.block_on(Renderer::new_headless(64, 48));
+
+        let image = renderer.render_to_texture(64, 48);
+
+        assert_eq!(image.width(), 64);
+        assert_eq!(image.height(), 48);
+
+        // Every pixel is either the background clear color or a shaded
+        // mesh fragment, both fully opaque, so alpha should never be 0
+        // even where the scene doesn't cover a pixel.
+        assert_eq!(image.get_pixel(0, 0)[3], 255);
+    }
+}
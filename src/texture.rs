@@ -0,0 +1,229 @@
+//! GPU texture and sampler upload for glTF materials.
+//!
+//! `gltf::import` (used by [`crate::gltf::load_gltf`]) already resolves
+//! embedded buffers, base64 data URIs, and external image files through the
+//! `image` crate and hands back decoded pixels as [`gltf::image::Data`]. This
+//! module only needs to re-pack those pixels into a format wgpu accepts and
+//! upload them.
+//!
+//! That decoding only happens if the `gltf` crate's own `import` Cargo
+//! feature is enabled (it pulls in `image` transitively) - without it,
+//! `gltf::import` never produces `gltf::image::Data` at all, so there's no
+//! silent fallback to watch out for here; an import with external or
+//! data-URI images would simply fail to compile/build against this crate.
+
+/// An uploaded GPU texture plus the view and sampler used to read it.
+pub struct Texture {
+    pub texture: wgpu::Texture,
+    pub view: wgpu::TextureView,
+    pub sampler: wgpu::Sampler,
+}
+
+impl Texture {
+    /// Uploads a decoded glTF image as an sRGB (or linear) GPU texture,
+    /// honoring the glTF sampler's wrap and filter modes.
+    pub fn from_gltf_image(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        image: &gltf::image::Data,
+        sampler: &gltf::texture::Sampler,
+        srgb: bool,
+        label: &str,
+    ) -> Texture {
+        let (rgba, format) = to_rgba8(image, srgb);
+
+        let size = wgpu::Extent3d {
+            width: image.width,
+            height: image.height,
+            depth_or_array_layers: 1,
+        };
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &rgba,
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * image.width),
+                rows_per_image: Some(image.height),
+            },
+            size,
+        );
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&to_sampler_descriptor(sampler));
+
+        Texture {
+            texture,
+            view,
+            sampler,
+        }
+    }
+
+    /// A 1x1 opaque white texture, used so primitives without a base-color
+    /// texture can still bind a material group with the same layout.
+    pub fn white_1x1(device: &wgpu::Device, queue: &wgpu::Queue) -> Texture {
+        let size = wgpu::Extent3d {
+            width: 1,
+            height: 1,
+            depth_or_array_layers: 1,
+        };
+
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("White Fallback Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+
+        queue.write_texture(
+            wgpu::TexelCopyTextureInfo {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &[255, 255, 255, 255],
+            wgpu::TexelCopyBufferLayout {
+                offset: 0,
+                bytes_per_row: Some(4),
+                rows_per_image: Some(1),
+            },
+            size,
+        );
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
+
+        Texture {
+            texture,
+            view,
+            sampler,
+        }
+    }
+}
+
+/// Re-packs a decoded glTF image into tightly-packed RGBA8, since wgpu has no
+/// 3-channel texture formats.
+fn to_rgba8(image: &gltf::image::Data, srgb: bool) -> (Vec<u8>, wgpu::TextureFormat) {
+    use gltf::image::Format;
+
+    let format = if srgb {
+        wgpu::TextureFormat::Rgba8UnormSrgb
+    } else {
+        wgpu::TextureFormat::Rgba8Unorm
+    };
+
+    let rgba = match image.format {
+        Format::R8G8B8A8 => image.pixels.clone(),
+        Format::R8G8B8 => image
+            .pixels
+            .chunks_exact(3)
+            .flat_map(|p| [p[0], p[1], p[2], 255])
+            .collect(),
+        Format::R8 => image.pixels.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        Format::R8G8 => image
+            .pixels
+            .chunks_exact(2)
+            .flat_map(|p| [p[0], p[1], 0, 255])
+            .collect(),
+        // 16-bit and floating-point sources aren't used for glTF base-color
+        // textures in practice; fall back to opaque white rather than
+        // misinterpreting the bytes.
+        _ => vec![255u8; (image.width * image.height * 4) as usize],
+    };
+
+    (rgba, format)
+}
+
+fn to_sampler_descriptor(sampler: &gltf::texture::Sampler) -> wgpu::SamplerDescriptor<'static> {
+    wgpu::SamplerDescriptor {
+        address_mode_u: wrap_mode(sampler.wrap_s()),
+        address_mode_v: wrap_mode(sampler.wrap_t()),
+        address_mode_w: wgpu::AddressMode::Repeat,
+        mag_filter: mag_filter(sampler.mag_filter()),
+        min_filter: min_filter(sampler.min_filter()),
+        mipmap_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    }
+}
+
+fn wrap_mode(mode: gltf::texture::WrappingMode) -> wgpu::AddressMode {
+    match mode {
+        gltf::texture::WrappingMode::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+        gltf::texture::WrappingMode::MirroredRepeat => wgpu::AddressMode::MirrorRepeat,
+        gltf::texture::WrappingMode::Repeat => wgpu::AddressMode::Repeat,
+    }
+}
+
+fn mag_filter(filter: Option<gltf::texture::MagFilter>) -> wgpu::FilterMode {
+    match filter {
+        Some(gltf::texture::MagFilter::Nearest) => wgpu::FilterMode::Nearest,
+        _ => wgpu::FilterMode::Linear,
+    }
+}
+
+fn min_filter(filter: Option<gltf::texture::MinFilter>) -> wgpu::FilterMode {
+    use gltf::texture::MinFilter;
+    match filter {
+        Some(MinFilter::Nearest)
+        | Some(MinFilter::NearestMipmapNearest)
+        | Some(MinFilter::NearestMipmapLinear) => wgpu::FilterMode::Nearest,
+        _ => wgpu::FilterMode::Linear,
+    }
+}
+
+/// Bind group layout for the group-2 material: base-color texture, sampler,
+/// and base-color factor uniform.
+pub fn material_bind_group_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        label: Some("Material Bind Group Layout"),
+        entries: &[
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+        ],
+    })
+}